@@ -1,30 +1,124 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::Duration;
 
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use rand::{thread_rng, Rng};
 use serde::Deserialize;
 use tokio::time::sleep;
 use url::Url;
 
-use crate::{errors::KuponError, Health, HealthStatus, Match, ServerInfo};
+use crate::{errors::KuponError, Health, HealthStatus, Match, Script, ServerInfo};
 
 const DEFAULT_ENDPOINT: &str = "http://localhost:1442";
 
+/// Controls how a `Client` retries requests that Kupo (or the network)
+/// failed transiently, with exponential backoff and jitter between attempts.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    base_delay: Duration,
+    min_multiplier: f32,
+    max_multiplier: f32,
+    max_total_delay: Option<Duration>,
+    retryable_statuses: Vec<u16>,
+    retry_on_request_failed: bool,
+}
+
+impl RetryPolicy {
+    /// Never retry; fail on the first error.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn max_retries(self, max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..self
+        }
+    }
+
+    pub fn base_delay(self, base_delay: Duration) -> Self {
+        Self { base_delay, ..self }
+    }
+
+    pub fn backoff_multiplier(self, min: f32, max: f32) -> Self {
+        Self {
+            min_multiplier: min,
+            max_multiplier: max,
+            ..self
+        }
+    }
+
+    /// Caps the total time spent sleeping between retries for a single call.
+    pub fn max_total_delay(self, max_total_delay: Duration) -> Self {
+        Self {
+            max_total_delay: Some(max_total_delay),
+            ..self
+        }
+    }
+
+    /// Sets the HTTP status codes that are considered transient and worth
+    /// retrying, e.g. `503` for a Kupo instance that isn't ready yet.
+    pub fn retryable_statuses(self, retryable_statuses: Vec<u16>) -> Self {
+        Self {
+            retryable_statuses,
+            ..self
+        }
+    }
+
+    /// Whether to retry requests that failed before getting a response at
+    /// all, e.g. a dropped connection (`KuponError::RequestFailed`).
+    pub fn retry_on_request_failed(self, retry_on_request_failed: bool) -> Self {
+        Self {
+            retry_on_request_failed,
+            ..self
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay: Duration::from_millis(100),
+            min_multiplier: 1.5,
+            max_multiplier: 2.5,
+            max_total_delay: None,
+            retryable_statuses: vec![503],
+            retry_on_request_failed: false,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Builder {
     endpoint: Option<String>,
-    retries: usize,
+    retry_policy: RetryPolicy,
 }
 
 impl Builder {
     pub fn with_endpoint<T: Into<String>>(endpoint: T) -> Self {
         Self {
             endpoint: Some(endpoint.into()),
-            retries: 0,
+            retry_policy: RetryPolicy::default(),
         }
     }
 
     pub fn with_retries(self, retries: usize) -> Self {
-        Self { retries, ..self }
+        Self {
+            retry_policy: self.retry_policy.max_retries(retries),
+            ..self
+        }
+    }
+
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
     }
 
     pub fn build(self) -> Result<Client, KuponError> {
@@ -34,7 +128,7 @@ impl Builder {
         Ok(Client {
             client,
             endpoint,
-            retries: self.retries,
+            retry_policy: self.retry_policy,
         })
     }
 }
@@ -42,10 +136,53 @@ impl Builder {
 pub struct Client {
     client: reqwest::Client,
     endpoint: Url,
-    retries: usize,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
+    /// Executes a request built by `build_request`, retrying according to
+    /// `self.retry_policy` on retryable statuses or (if configured)
+    /// transport failures, with jittered exponential backoff between tries.
+    async fn execute_with_retry(
+        &self,
+        build_request: impl Fn() -> Result<reqwest::Request, KuponError>,
+    ) -> Result<reqwest::Response, KuponError> {
+        let policy = &self.retry_policy;
+        let mut retries = policy.max_retries;
+        let mut delay = policy.base_delay;
+        let mut total_delay = Duration::ZERO;
+        loop {
+            let request = build_request()?;
+            match self.client.execute(request).await {
+                Ok(response) => {
+                    if retries == 0 || !policy.retryable_statuses.contains(&response.status().as_u16())
+                    {
+                        return Ok(response);
+                    }
+                }
+                Err(error) => {
+                    if retries == 0 || !policy.retry_on_request_failed {
+                        return Err(KuponError::RequestFailed(error));
+                    }
+                }
+            }
+
+            let mut wait = delay;
+            if let Some(max_total_delay) = policy.max_total_delay {
+                if total_delay >= max_total_delay {
+                    let request = build_request()?;
+                    return Ok(self.client.execute(request).await?);
+                }
+                wait = wait.min(max_total_delay - total_delay);
+            }
+
+            sleep(wait).await;
+            total_delay += wait;
+            retries -= 1;
+            delay = delay.mul_f32(thread_rng().gen_range(policy.min_multiplier..policy.max_multiplier));
+        }
+    }
+
     pub async fn health(&self) -> Health {
         match self.try_health().await {
             Ok(health) => health,
@@ -57,15 +194,17 @@ impl Client {
     }
 
     async fn try_health(&self) -> Result<Health, KuponError> {
-        let mut health_url = self.endpoint.clone();
-        health_url.set_path("health");
-
-        let request = self
-            .client
-            .get(health_url)
-            .header("Accept", "application/json")
-            .build()?;
-        let response = self.client.execute(request).await?;
+        let response = self
+            .execute_with_retry(|| {
+                let mut health_url = self.endpoint.clone();
+                health_url.set_path("health");
+                Ok(self
+                    .client
+                    .get(health_url)
+                    .header("Accept", "application/json")
+                    .build()?)
+            })
+            .await?;
         let mut status = match response.status().as_u16() {
             200 => HealthStatus::Healthy,
             202 => HealthStatus::Syncing,
@@ -84,38 +223,332 @@ impl Client {
     }
 
     pub async fn matches(&self, options: &MatchOptions) -> Result<Vec<Match>, KuponError> {
-        let mut retries = self.retries;
-        let mut delay = Duration::from_millis(100);
-        loop {
-            let match_url = options.to_url(&self.endpoint)?;
-            let request = self.client.get(match_url).build()?;
-            let response = self.client.execute(request).await?;
-            let status = response.status();
-            match response.json().await? {
-                MatchResponse::Success(matches) => return Ok(matches),
-                MatchResponse::Failure { hint } => {
-                    if retries == 0 || status.as_u16() != 503 {
-                        return Err(KuponError::KupoError(hint));
+        let response = self
+            .execute_with_retry(|| {
+                let match_url = options.to_url(&self.endpoint)?;
+                Ok(self.client.get(match_url).build()?)
+            })
+            .await?;
+        match response.json().await? {
+            MatchResponse::Success(matches) => Ok(matches),
+            MatchResponse::Failure { hint } => Err(KuponError::KupoError(hint)),
+        }
+    }
+
+    /// Streams matches page by page, yielding them one at a time.
+    ///
+    /// Starts from `options.created_after` when it is a slot number (or
+    /// genesis if it is unset or a block header hash) and walks forward,
+    /// advancing the lower bound to the highest `created_at.slot_no` seen in
+    /// each page. Each request only moves the lower bound; no upper slot bound
+    /// is added beyond `options.created_before`, so a page holds every match
+    /// from the current lower bound onward and is buffered whole. Every
+    /// yielded match must carry a `created_at` slot, since that's what the
+    /// cursor advances on; a match without one is reported as an error rather
+    /// than silently stalling the stream. A rollback is detected only when a
+    /// match at the last observed slot comes back with a different header
+    /// hash; the window then restarts from genesis.
+    pub fn matches_stream<'a>(
+        &'a self,
+        options: &'a MatchOptions,
+    ) -> impl Stream<Item = Result<Match, KuponError>> + 'a {
+        struct State<'a> {
+            client: &'a Client,
+            options: MatchOptions,
+            cursor: PaginationCursor,
+            buffer: VecDeque<Match>,
+            done: bool,
+        }
+
+        let start_slot = options
+            .created_after
+            .as_ref()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        let state = State {
+            client: self,
+            options: options.clone(),
+            cursor: PaginationCursor::new(start_slot),
+            buffer: VecDeque::new(),
+            done: false,
+        };
+
+        stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(matc) = state.buffer.pop_front() {
+                    return Some((Ok(matc), state));
+                }
+
+                if state.done {
+                    return None;
+                }
+
+                let window = state
+                    .options
+                    .clone()
+                    .created_after(state.cursor.lower_bound().to_string());
+
+                let page = match state.client.matches(&window).await {
+                    Ok(page) => page,
+                    Err(error) => {
+                        state.done = true;
+                        return Some((Err(error), state));
+                    }
+                };
+
+                match state.cursor.ingest(page) {
+                    Ok(Ingested::Done) => {
+                        state.done = true;
+                    }
+                    Ok(Ingested::RolledBack) => {
+                        // The cursor already reset to genesis; re-query the new window.
+                    }
+                    Ok(Ingested::Matches(matches)) => {
+                        state.buffer.extend(matches);
+                    }
+                    Err(error) => {
+                        state.done = true;
+                        return Some((Err(error), state));
                     }
-                    sleep(delay).await;
-                    retries -= 1;
-                    delay = delay.mul_f32(thread_rng().gen_range(1.5..2.5))
                 }
-            };
-        }
+            }
+        })
     }
 
     pub async fn datum(&self, hash: &str) -> Result<Option<String>, KuponError> {
-        let mut datum_url = self.endpoint.clone();
-        datum_url.set_path(&format!("v1/datums/{}", hash));
-        let request = self.client.get(datum_url).build()?;
-        let response = self.client.execute(request).await?.json().await?;
-        match response {
+        let response = self
+            .execute_with_retry(|| {
+                let mut datum_url = self.endpoint.clone();
+                datum_url.set_path(&format!("v1/datums/{}", hash));
+                Ok(self.client.get(datum_url).build()?)
+            })
+            .await?;
+        match response.json().await? {
             Some(DatumResponse::Success { datum }) => Ok(Some(datum)),
             Some(DatumResponse::Failure { hint }) => Err(KuponError::KupoError(hint)),
             None => Ok(None),
         }
     }
+
+    pub async fn script(&self, hash: &str) -> Result<Option<Script>, KuponError> {
+        let response = self
+            .execute_with_retry(|| {
+                let mut script_url = self.endpoint.clone();
+                script_url.set_path(&format!("v1/scripts/{}", hash));
+                Ok(self.client.get(script_url).build()?)
+            })
+            .await?;
+        match response.json().await? {
+            Some(ScriptResponse::Success(script)) => Ok(Some(script)),
+            Some(ScriptResponse::Failure { hint }) => Err(KuponError::KupoError(hint)),
+            None => Ok(None),
+        }
+    }
+
+    /// Resolves many datums at once, fanning out requests with a bounded
+    /// concurrency instead of awaiting them one at a time. Duplicate hashes
+    /// are only requested once.
+    pub async fn resolve_datums(
+        &self,
+        hashes: &[&str],
+        concurrency: usize,
+    ) -> Result<HashMap<String, Option<String>>, KuponError> {
+        if concurrency == 0 {
+            return Err(KuponError::InvalidQuery(
+                "concurrency must be at least 1".into(),
+            ));
+        }
+        let unique: HashSet<&str> = hashes.iter().copied().collect();
+        stream::iter(unique)
+            .map(|hash| async move {
+                let datum = self.datum(hash).await?;
+                Ok::<_, KuponError>((hash.to_string(), datum))
+            })
+            .buffer_unordered(concurrency)
+            .try_collect()
+            .await
+    }
+
+    /// Resolves many scripts at once, fanning out requests with a bounded
+    /// concurrency instead of awaiting them one at a time. Duplicate hashes
+    /// are only requested once.
+    pub async fn resolve_scripts(
+        &self,
+        hashes: &[&str],
+        concurrency: usize,
+    ) -> Result<HashMap<String, Option<Script>>, KuponError> {
+        if concurrency == 0 {
+            return Err(KuponError::InvalidQuery(
+                "concurrency must be at least 1".into(),
+            ));
+        }
+        let unique: HashSet<&str> = hashes.iter().copied().collect();
+        stream::iter(unique)
+            .map(|hash| async move {
+                let script = self.script(hash).await?;
+                Ok::<_, KuponError>((hash.to_string(), script))
+            })
+            .buffer_unordered(concurrency)
+            .try_collect()
+            .await
+    }
+
+    /// Lists the patterns currently tracked by the Kupo instance.
+    pub async fn patterns(&self) -> Result<Vec<String>, KuponError> {
+        let response = self
+            .execute_with_retry(|| {
+                let mut patterns_url = self.endpoint.clone();
+                patterns_url.set_path("patterns");
+                Ok(self.client.get(patterns_url).build()?)
+            })
+            .await?;
+        match response.json().await? {
+            PatternsResponse::Success(patterns) => Ok(patterns),
+            PatternsResponse::Failure { hint } => Err(KuponError::KupoError(hint)),
+        }
+    }
+
+    /// Adds a pattern, telling Kupo to start indexing matches for it.
+    pub async fn add_pattern(&self, pattern: &str) -> Result<bool, KuponError> {
+        let response = self
+            .execute_with_retry(|| {
+                let mut pattern_url = self.endpoint.clone();
+                pattern_url.set_path(&pattern_path(pattern));
+                Ok(self.client.put(pattern_url).build()?)
+            })
+            .await?;
+        match response.json().await? {
+            AddPatternResponse::Success { created } => Ok(created),
+            AddPatternResponse::Failure { hint } => Err(KuponError::KupoError(hint)),
+        }
+    }
+
+    /// Removes a pattern, telling Kupo to stop indexing matches for it.
+    pub async fn remove_pattern(&self, pattern: &str) -> Result<bool, KuponError> {
+        let response = self
+            .execute_with_retry(|| {
+                let mut pattern_url = self.endpoint.clone();
+                pattern_url.set_path(&pattern_path(pattern));
+                Ok(self.client.delete(pattern_url).build()?)
+            })
+            .await?;
+        match response.json().await? {
+            RemovePatternResponse::Success { deleted } => Ok(deleted),
+            RemovePatternResponse::Failure { hint } => Err(KuponError::KupoError(hint)),
+        }
+    }
+}
+
+/// Builds the path Kupo expects for a single pattern, e.g.
+/// `patterns/addr1...*` or `patterns/*@tx_id`.
+fn pattern_path(pattern: &str) -> String {
+    format!("patterns/{}", pattern)
+}
+
+/// Outcome of feeding one fetched page into a [`PaginationCursor`].
+#[derive(Debug)]
+enum Ingested {
+    /// Fresh matches to yield, with the cursor already advanced past them.
+    Matches(Vec<Match>),
+    /// A reorg was detected; the cursor reset to genesis and the caller
+    /// should re-query the (now different) window before yielding anything.
+    RolledBack,
+    /// The page was empty: there is nothing left to stream.
+    Done,
+}
+
+/// Tracks the slot-window cursor driving [`Client::matches_stream`],
+/// independent of how pages are actually fetched so it can be tested with
+/// synthetic page sequences.
+#[derive(Debug, Default)]
+struct PaginationCursor {
+    lower_bound: u64,
+    last_seen: Option<(u64, String)>,
+    boundary_keys: HashSet<(String, u64)>,
+}
+
+impl PaginationCursor {
+    fn new(start_slot: u64) -> Self {
+        Self {
+            lower_bound: start_slot,
+            ..Self::default()
+        }
+    }
+
+    fn lower_bound(&self) -> u64 {
+        self.lower_bound
+    }
+
+    /// Processes one page fetched with `created_after(self.lower_bound())`.
+    ///
+    /// `created_after` is inclusive, so the boundary slot can reappear in
+    /// the next page; matches already yielded at that slot are filtered out
+    /// rather than re-emitted, and a mismatched `header_hash` at that slot
+    /// means the chain rolled back underneath us. Every match in a non-empty
+    /// page must carry `created_at`, since that's the only thing the cursor
+    /// can advance on.
+    fn ingest(&mut self, page: Vec<Match>) -> Result<Ingested, KuponError> {
+        if page.is_empty() {
+            return Ok(Ingested::Done);
+        }
+
+        for matc in &page {
+            if matc.created_at.is_none() {
+                return Err(KuponError::InvalidQuery(
+                    "matches_stream requires every match to carry a created_at slot".into(),
+                ));
+            }
+        }
+
+        if let Some((last_slot, last_header_hash)) = &self.last_seen {
+            let rolled_back = page.iter().any(|matc| {
+                let block = matc.created_at.as_ref().expect("checked above");
+                block.slot_no == *last_slot && &block.header_hash != last_header_hash
+            });
+            if rolled_back {
+                self.lower_bound = 0;
+                self.last_seen = None;
+                self.boundary_keys.clear();
+                return Ok(Ingested::RolledBack);
+            }
+        }
+
+        let tip = page
+            .iter()
+            .map(|matc| matc.created_at.clone().expect("checked above"))
+            .max_by_key(|block| block.slot_no)
+            .expect("page is non-empty");
+
+        let previous_boundary = self.last_seen.as_ref().map(|(slot, _)| *slot);
+
+        // Computed from the full page (not the post-dedup `fresh` below), so
+        // identifiers already seen at `tip.slot_no` aren't forgotten just
+        // because this round happened to filter all of them out.
+        let new_boundary_keys: HashSet<(String, u64)> = page
+            .iter()
+            .filter(|matc| matc.created_at.as_ref().expect("checked above").slot_no == tip.slot_no)
+            .map(|matc| (matc.transaction_id.clone(), matc.output_index))
+            .collect();
+
+        let fresh: Vec<Match> = page
+            .into_iter()
+            .filter(|matc| {
+                let block = matc.created_at.as_ref().expect("checked above");
+                let at_previous_boundary = previous_boundary == Some(block.slot_no);
+                !at_previous_boundary
+                    || !self
+                        .boundary_keys
+                        .contains(&(matc.transaction_id.clone(), matc.output_index))
+            })
+            .collect();
+
+        self.boundary_keys = new_boundary_keys;
+        self.last_seen = Some((tip.slot_no, tip.header_hash.clone()));
+        self.lower_bound = tip.slot_no;
+
+        Ok(Ingested::Matches(fresh))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -154,6 +587,21 @@ impl TransactionIdOptions {
     }
 }
 
+#[derive(Clone, Debug)]
+pub enum Order {
+    MostRecentFirst,
+    OldestFirst,
+}
+
+impl Order {
+    fn as_query_value(&self) -> &'static str {
+        match self {
+            Order::MostRecentFirst => "most_recent_first",
+            Order::OldestFirst => "oldest_first",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct MatchOptions {
     spent_status: Option<SpentStatus>,
@@ -161,6 +609,11 @@ pub struct MatchOptions {
     credential: Option<String>,
     asset: Option<AssetIdOptions>,
     transaction: Option<TransactionIdOptions>,
+    created_after: Option<String>,
+    created_before: Option<String>,
+    spent_after: Option<String>,
+    spent_before: Option<String>,
+    order: Option<Order>,
 }
 
 impl MatchOptions {
@@ -236,6 +689,46 @@ impl MatchOptions {
         }
     }
 
+    /// Only include matches created at or after this slot (or block header hash).
+    pub fn created_after<T: Into<String>>(self, slot_or_header_hash: T) -> Self {
+        Self {
+            created_after: Some(slot_or_header_hash.into()),
+            ..self
+        }
+    }
+
+    /// Only include matches created at or before this slot (or block header hash).
+    pub fn created_before<T: Into<String>>(self, slot_or_header_hash: T) -> Self {
+        Self {
+            created_before: Some(slot_or_header_hash.into()),
+            ..self
+        }
+    }
+
+    /// Only include matches spent at or after this slot (or block header hash).
+    pub fn spent_after<T: Into<String>>(self, slot_or_header_hash: T) -> Self {
+        Self {
+            spent_after: Some(slot_or_header_hash.into()),
+            ..self
+        }
+    }
+
+    /// Only include matches spent at or before this slot (or block header hash).
+    pub fn spent_before<T: Into<String>>(self, slot_or_header_hash: T) -> Self {
+        Self {
+            spent_before: Some(slot_or_header_hash.into()),
+            ..self
+        }
+    }
+
+    /// Order the results by creation slot, either newest or oldest first.
+    pub fn order(self, order: Order) -> Self {
+        Self {
+            order: Some(order),
+            ..self
+        }
+    }
+
     pub(crate) fn to_url(&self, endpoint: &Url) -> Result<Url, KuponError> {
         if self.address.is_some() && self.credential.is_some() {
             return Err(KuponError::InvalidQuery(
@@ -281,6 +774,26 @@ impl MatchOptions {
             None => {}
         };
 
+        if let Some(created_after) = &self.created_after {
+            query.append_pair("created_after", created_after);
+        }
+
+        if let Some(created_before) = &self.created_before {
+            query.append_pair("created_before", created_before);
+        }
+
+        if let Some(spent_after) = &self.spent_after {
+            query.append_pair("spent_after", spent_after);
+        }
+
+        if let Some(spent_before) = &self.spent_before {
+            query.append_pair("spent_before", spent_before);
+        }
+
+        if let Some(order) = &self.order {
+            query.append_pair("order", order.as_query_value());
+        }
+
         drop(query);
 
         if let Some(pattern) = pattern {
@@ -313,3 +826,255 @@ enum DatumResponse {
     Success { datum: String },
     Failure { hint: String },
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum ScriptResponse {
+    Success(Script),
+    Failure { hint: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum PatternsResponse {
+    Success(Vec<String>),
+    Failure { hint: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum AddPatternResponse {
+    Success { created: bool },
+    Failure { hint: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum RemovePatternResponse {
+    Success { deleted: bool },
+    Failure { hint: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::Duration;
+
+    use rand::{thread_rng, Rng};
+    use url::Url;
+
+    use crate::{errors::KuponError, BlockReference, Match, MatchValue};
+
+    use super::{
+        pattern_path, AddPatternResponse, Builder, Ingested, MatchOptions, Order,
+        PaginationCursor, RemovePatternResponse, RetryPolicy,
+    };
+
+    fn matc(slot_no: u64, header_hash: &str, transaction_id: &str, output_index: u64) -> Match {
+        Match {
+            transaction_index: 0,
+            transaction_id: transaction_id.into(),
+            output_index,
+            address: "addr_test".into(),
+            value: MatchValue {
+                coins: 0,
+                assets: BTreeMap::new(),
+            },
+            datum: None,
+            script_hash: None,
+            created_at: Some(BlockReference {
+                slot_no,
+                header_hash: header_hash.into(),
+            }),
+            spent_at: None,
+        }
+    }
+
+    #[test]
+    fn should_advance_lower_bound_to_tip_slot() {
+        let mut cursor = PaginationCursor::new(0);
+        let page = vec![matc(10, "hash-a", "tx1", 0), matc(20, "hash-b", "tx2", 0)];
+        match cursor.ingest(page) {
+            Ok(Ingested::Matches(matches)) => assert_eq!(matches.len(), 2),
+            other => panic!("expected matches, got {:?}", other),
+        }
+        assert_eq!(cursor.lower_bound(), 20);
+    }
+
+    #[test]
+    fn should_not_reyield_matches_already_seen_at_the_boundary_slot() {
+        let mut cursor = PaginationCursor::new(0);
+        cursor
+            .ingest(vec![matc(10, "hash-a", "tx1", 0)])
+            .expect("first page");
+        assert_eq!(cursor.lower_bound(), 10);
+
+        // created_after(10) is inclusive, so the same boundary match
+        // reappears alongside the new one.
+        let page = vec![matc(10, "hash-a", "tx1", 0), matc(15, "hash-a", "tx2", 0)];
+        match cursor.ingest(page) {
+            Ok(Ingested::Matches(matches)) => {
+                assert_eq!(matches.len(), 1);
+                assert_eq!(matches[0].transaction_id, "tx2");
+            }
+            other => panic!("expected matches, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_not_reyield_boundary_match_when_caught_up_to_the_tip() {
+        let mut cursor = PaginationCursor::new(0);
+        let page = vec![matc(10, "hash-a", "tx1", 0)];
+
+        cursor.ingest(page.clone()).expect("first page");
+
+        // The stream is caught up to the tip, so every subsequent poll
+        // re-fetches the exact same (still inclusive) boundary slot.
+        for round in 1..=5 {
+            match cursor.ingest(page.clone()) {
+                Ok(Ingested::Matches(matches)) => {
+                    assert!(
+                        matches.is_empty(),
+                        "round {round} re-yielded already-seen matches: {matches:?}"
+                    );
+                }
+                other => panic!("round {round}: expected matches, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn should_detect_rollback_when_boundary_header_hash_changes() {
+        let mut cursor = PaginationCursor::new(0);
+        cursor
+            .ingest(vec![matc(10, "hash-a", "tx1", 0)])
+            .expect("first page");
+        assert_eq!(cursor.lower_bound(), 10);
+
+        // The chain reorganized: the block at slot 10 now has a different hash.
+        let page = vec![matc(10, "hash-b", "tx1", 0)];
+        assert!(matches!(cursor.ingest(page), Ok(Ingested::RolledBack)));
+        assert_eq!(cursor.lower_bound(), 0);
+    }
+
+    #[test]
+    fn should_terminate_on_empty_page() {
+        let mut cursor = PaginationCursor::new(0);
+        assert!(matches!(cursor.ingest(vec![]), Ok(Ingested::Done)));
+    }
+
+    #[test]
+    fn should_error_instead_of_stalling_when_created_at_is_missing() {
+        let mut cursor = PaginationCursor::new(0);
+        let mut without_slot = matc(10, "hash-a", "tx1", 0);
+        without_slot.created_at = None;
+        assert!(cursor.ingest(vec![without_slot]).is_err());
+    }
+
+    #[tokio::test]
+    async fn should_reject_zero_concurrency_in_resolve_datums() {
+        let client = Builder::with_endpoint("http://localhost:1442").build().unwrap();
+        let result = client.resolve_datums(&["deadbeef"], 0).await;
+        assert!(matches!(result, Err(KuponError::InvalidQuery(_))));
+    }
+
+    #[tokio::test]
+    async fn should_reject_zero_concurrency_in_resolve_scripts() {
+        let client = Builder::with_endpoint("http://localhost:1442").build().unwrap();
+        let result = client.resolve_scripts(&["deadbeef"], 0).await;
+        assert!(matches!(result, Err(KuponError::InvalidQuery(_))));
+    }
+
+    #[test]
+    fn should_build_match_url_with_slot_range_and_order_params() {
+        let options = MatchOptions::default()
+            .created_after("100")
+            .created_before("200")
+            .spent_after("50")
+            .spent_before("150")
+            .order(Order::OldestFirst);
+        let endpoint = Url::parse("http://localhost:1442").unwrap();
+        let url = options.to_url(&endpoint).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(params.get("created_after"), Some(&"100".to_string()));
+        assert_eq!(params.get("created_before"), Some(&"200".to_string()));
+        assert_eq!(params.get("spent_after"), Some(&"50".to_string()));
+        assert_eq!(params.get("spent_before"), Some(&"150".to_string()));
+        assert_eq!(params.get("order"), Some(&"oldest_first".to_string()));
+    }
+
+    #[test]
+    fn should_omit_order_param_when_unset() {
+        let options = MatchOptions::default();
+        let endpoint = Url::parse("http://localhost:1442").unwrap();
+        let url = options.to_url(&endpoint).unwrap();
+
+        assert!(url.query_pairs().all(|(key, _)| key != "order"));
+    }
+
+    #[test]
+    fn should_default_retry_policy_to_no_retries_and_503_only() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_retries, 0);
+        assert_eq!(policy.retryable_statuses, vec![503]);
+        assert!(!policy.retry_on_request_failed);
+    }
+
+    #[test]
+    fn should_override_retry_policy_settings_via_builder() {
+        let policy = RetryPolicy::default()
+            .max_retries(5)
+            .base_delay(Duration::from_millis(10))
+            .backoff_multiplier(1.0, 2.0)
+            .max_total_delay(Duration::from_secs(1))
+            .retryable_statuses(vec![429, 503])
+            .retry_on_request_failed(true);
+
+        assert_eq!(policy.max_retries, 5);
+        assert_eq!(policy.base_delay, Duration::from_millis(10));
+        assert_eq!(policy.min_multiplier, 1.0);
+        assert_eq!(policy.max_multiplier, 2.0);
+        assert_eq!(policy.max_total_delay, Some(Duration::from_secs(1)));
+        assert_eq!(policy.retryable_statuses, vec![429, 503]);
+        assert!(policy.retry_on_request_failed);
+    }
+
+    #[test]
+    fn should_sample_jitter_multiplier_within_configured_bounds() {
+        let policy = RetryPolicy::default().backoff_multiplier(1.2, 1.8);
+        for _ in 0..100 {
+            let multiplier = thread_rng().gen_range(policy.min_multiplier..policy.max_multiplier);
+            assert!((1.2..1.8).contains(&multiplier));
+        }
+    }
+
+    #[test]
+    fn should_build_pattern_path() {
+        assert_eq!(
+            pattern_path("addr1w9qzpelu9hn45pefc0xr4ac4kdxeswq7pndul2vuj59u8tqaxdznu"),
+            "patterns/addr1w9qzpelu9hn45pefc0xr4ac4kdxeswq7pndul2vuj59u8tqaxdznu"
+        );
+        assert_eq!(pattern_path("*@tx_id"), "patterns/*@tx_id");
+    }
+
+    #[test]
+    fn should_parse_add_pattern_response() {
+        let success: AddPatternResponse = serde_json::from_str(r#"{"created":true}"#).unwrap();
+        assert!(matches!(success, AddPatternResponse::Success { created: true }));
+
+        let failure: AddPatternResponse =
+            serde_json::from_str(r#"{"hint":"pattern is invalid"}"#).unwrap();
+        assert!(matches!(failure, AddPatternResponse::Failure { .. }));
+    }
+
+    #[test]
+    fn should_parse_remove_pattern_response() {
+        let success: RemovePatternResponse = serde_json::from_str(r#"{"deleted":true}"#).unwrap();
+        assert!(matches!(success, RemovePatternResponse::Success { deleted: true }));
+
+        let failure: RemovePatternResponse =
+            serde_json::from_str(r#"{"hint":"pattern is invalid"}"#).unwrap();
+        assert!(matches!(failure, RemovePatternResponse::Failure { .. }));
+    }
+}
@@ -71,6 +71,24 @@ pub struct BlockReference {
     pub header_hash: String,
 }
 
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptLanguage {
+    #[serde(rename = "native")]
+    Native,
+    #[serde(rename = "plutus:v1")]
+    PlutusV1,
+    #[serde(rename = "plutus:v2")]
+    PlutusV2,
+    #[serde(rename = "plutus:v3")]
+    PlutusV3,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Script {
+    pub language: ScriptLanguage,
+    pub script: String,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{AssetId, MatchValue};